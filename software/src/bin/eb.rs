@@ -1,16 +1,28 @@
-//! Etherbone CLI - Direct register access via FT601
+//! Etherbone CLI - Direct register access via FT601, UDP or TCP
 //!
 //! Usage:
-//!   eb read <addr>              Read a single register
-//!   eb read <addr> <count>      Read multiple registers
-//!   eb write <addr> <value>     Write a single register
-//!   eb probe                    Check if device responds
-//!   eb dump <addr> <count>      Hex dump memory region
+//!   eb [--udp <host:port> | --tcp <host:port>] <command> ...
 //!
+//!   eb read [--fifo] <addr>              Read a single register
+//!   eb read [--fifo] <addr> <count>      Read multiple registers
+//!   eb write [--fifo] <addr> <value>     Write a single register
+//!   eb probe                             Check if device responds
+//!   eb dump [--fifo] <addr> <count>      Hex dump memory region
+//!   eb enum <sdb_addr>                   Walk the SDB ROM and list devices
+//!   eb load <addr> <file>                Write a binary file into device memory
+//!   eb save <addr> <count> <file>        Read a region to a binary file
+//!   eb verify <addr> <file>              Write then read back, report first mismatch
+//!
+//! `--fifo` holds the address constant across the whole burst instead of
+//! auto-incrementing - for streaming a FIFO register rather than reading
+//! consecutive memory.
+//!
+//! With no --udp/--tcp option, eb talks to the first FT601 device found.
 //! Addresses and values can be specified in hex (0x...) or decimal.
 
 use ft601::Bridge;
 use std::env;
+use std::fs;
 use std::process::exit;
 
 fn parse_u32(s: &str) -> Result<u32, String> {
@@ -22,22 +34,48 @@ fn parse_u32(s: &str) -> Result<u32, String> {
 }
 
 fn print_usage() {
-    eprintln!("Etherbone CLI - Direct register access via FT601");
+    eprintln!("Etherbone CLI - Direct register access via FT601, UDP or TCP");
     eprintln!();
     eprintln!("Usage:");
-    eprintln!("  eb read <addr>              Read a single 32-bit register");
-    eprintln!("  eb read <addr> <count>      Read multiple consecutive registers");
-    eprintln!("  eb write <addr> <value>     Write a 32-bit register");
-    eprintln!("  eb probe                    Check if device responds");
-    eprintln!("  eb dump <addr> <count>      Hex dump memory region");
-    eprintln!("  eb list                     List available devices");
+    eprintln!("  eb [--udp <host:port> | --tcp <host:port>] <command> ...");
+    eprintln!();
+    eprintln!("  eb read [--fifo] <addr>              Read a single 32-bit register");
+    eprintln!("  eb read [--fifo] <addr> <count>      Read multiple registers");
+    eprintln!("  eb write [--fifo] <addr> <value>     Write a 32-bit register");
+    eprintln!("  eb probe                             Check if device responds");
+    eprintln!("  eb dump [--fifo] <addr> <count>      Hex dump memory region");
+    eprintln!("  eb enum <sdb_addr>                   Walk the SDB ROM and list devices");
+    eprintln!("  eb load <addr> <file>                Write a binary file into device memory");
+    eprintln!("  eb save <addr> <count> <file>        Read a region to a binary file");
+    eprintln!("  eb verify <addr> <file>               Write then read back, report first mismatch");
+    eprintln!("  eb list                               List available devices");
+    eprintln!();
+    eprintln!("--fifo holds the address constant across the burst instead of");
+    eprintln!("auto-incrementing, for streaming a FIFO register.");
     eprintln!();
+    eprintln!("With no --udp/--tcp option, eb talks to the first FT601 device found.");
     eprintln!("Addresses and values can be hex (0x...) or decimal.");
     eprintln!();
     eprintln!("Examples:");
     eprintln!("  eb read 0x12345678");
     eprintln!("  eb write 0x12345678 0xdeadbeef");
     eprintln!("  eb dump 0x10000000 64");
+    eprintln!("  eb --udp 192.168.1.50:1234 read 0x10000000");
+}
+
+/// Which transport to connect with, selected by `--udp`/`--tcp`
+enum Transport {
+    Ft601,
+    Udp(String),
+    Tcp(String),
+}
+
+fn open_bridge(transport: &Transport) -> Result<Bridge, Box<dyn std::error::Error>> {
+    match transport {
+        Transport::Ft601 => Ok(Bridge::open()?),
+        Transport::Udp(addr) => Ok(Bridge::connect_udp(addr)?),
+        Transport::Tcp(addr) => Ok(Bridge::connect_tcp(addr)?),
+    }
 }
 
 fn cmd_list() -> Result<(), Box<dyn std::error::Error>> {
@@ -53,8 +91,7 @@ fn cmd_list() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn cmd_probe() -> Result<(), Box<dyn std::error::Error>> {
-    let bridge = Bridge::open()?;
+fn cmd_probe(bridge: &Bridge) -> Result<(), Box<dyn std::error::Error>> {
     if bridge.probe()? {
         println!("Device responded to probe");
         Ok(())
@@ -64,8 +101,19 @@ fn cmd_probe() -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
-fn cmd_read(addr: u32, count: usize) -> Result<(), Box<dyn std::error::Error>> {
-    let bridge = Bridge::open()?;
+fn cmd_read(
+    bridge: &Bridge,
+    addr: u32,
+    count: usize,
+    fifo: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if fifo {
+        let values = bridge.read_fifo(addr, count)?;
+        for value in &values {
+            println!("0x{:08x}", value);
+        }
+        return Ok(());
+    }
 
     if count == 1 {
         let value = bridge.read(addr)?;
@@ -80,24 +128,44 @@ fn cmd_read(addr: u32, count: usize) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn cmd_write(addr: u32, value: u32) -> Result<(), Box<dyn std::error::Error>> {
-    let bridge = Bridge::open()?;
-    bridge.write(addr, value)?;
+fn cmd_write(
+    bridge: &Bridge,
+    addr: u32,
+    value: u32,
+    fifo: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if fifo {
+        bridge.write_fifo(addr, &[value])?;
+    } else {
+        bridge.write(addr, value)?;
+    }
     println!("Wrote 0x{:08x} to 0x{:08x}", value, addr);
     Ok(())
 }
 
-fn cmd_dump(addr: u32, count: usize) -> Result<(), Box<dyn std::error::Error>> {
-    let bridge = Bridge::open()?;
-
-    // Read in chunks
-    let addrs: Vec<u32> = (0..count as u32).map(|i| addr + i * 4).collect();
-    let values = bridge.read_burst(&addrs)?;
+fn cmd_dump(
+    bridge: &Bridge,
+    addr: u32,
+    count: usize,
+    fifo: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let values = if fifo {
+        bridge.read_fifo(addr, count)?
+    } else {
+        let addrs: Vec<u32> = (0..count as u32).map(|i| addr + i * 4).collect();
+        bridge.read_burst(&addrs)?
+    };
 
-    // Print hex dump
+    // Print hex dump. In --fifo mode every word was read from the same
+    // constant address, so label rows by word index instead of implying
+    // an auto-incrementing address.
     for (i, chunk) in values.chunks(4).enumerate() {
-        let line_addr = addr + (i as u32 * 16);
-        print!("{:08x}:", line_addr);
+        if fifo {
+            print!("[{:4}]:", i * 4);
+        } else {
+            let line_addr = addr + (i as u32 * 16);
+            print!("{:08x}:", line_addr);
+        }
 
         // Hex values
         for val in chunk {
@@ -126,34 +194,137 @@ fn cmd_dump(addr: u32, count: usize) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn cmd_enum(bridge: &Bridge, sdb_addr: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let devices = bridge.enumerate(sdb_addr)?;
+    if devices.is_empty() {
+        println!("No devices found in SDB ROM at 0x{:08x}", sdb_addr);
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<18} {:<10} {:<18} {:<18}",
+        "Name", "Vendor ID", "Device ID", "First", "Last"
+    );
+    for dev in &devices {
+        println!(
+            "{:<20} 0x{:016x} 0x{:08x} 0x{:016x} 0x{:016x}",
+            dev.name, dev.vendor_id, dev.device_id, dev.addr_first, dev.addr_last
+        );
+    }
+    Ok(())
+}
+
+fn cmd_load(bridge: &Bridge, addr: u32, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    bridge.load(addr, &data)?;
+    println!("Wrote {} bytes from {} to 0x{:08x}", data.len(), path, addr);
+    Ok(())
+}
+
+fn cmd_save(
+    bridge: &Bridge,
+    addr: u32,
+    count: usize,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let data = bridge.save(addr, count)?;
+    fs::write(path, &data)?;
+    println!("Read {} bytes from 0x{:08x} to {}", data.len(), addr, path);
+    Ok(())
+}
+
+fn cmd_verify(bridge: &Bridge, addr: u32, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    match bridge.verify(addr, &data)? {
+        None => {
+            println!("Verify OK: {} bytes match at 0x{:08x}", data.len(), addr);
+            Ok(())
+        }
+        Some(offset) => {
+            eprintln!(
+                "Verify FAILED: mismatch at offset {} (0x{:08x})",
+                offset,
+                addr + offset as u32
+            );
+            exit(1);
+        }
+    }
+}
+
+/// Pull a `--fifo` flag out of a command's positional arguments, wherever
+/// it appears, returning the remaining positional args alongside whether
+/// it was present
+fn strip_fifo_flag(args: &[String]) -> (Vec<String>, bool) {
+    let mut fifo = false;
+    let rest = args
+        .iter()
+        .filter(|a| {
+            if a.as_str() == "--fifo" {
+                fifo = true;
+                false
+            } else {
+                true
+            }
+        })
+        .cloned()
+        .collect();
+    (rest, fifo)
+}
+
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let all_args: Vec<String> = env::args().collect();
 
-    if args.len() < 2 {
+    if all_args.len() < 2 {
         print_usage();
         exit(1);
     }
 
-    let result = match args[1].as_str() {
+    let mut transport = Transport::Ft601;
+    let mut args = &all_args[1..];
+
+    match args[0].as_str() {
+        "--udp" | "--tcp" if args.len() < 2 => {
+            eprintln!("Error: {} requires a <host:port> argument", args[0]);
+            print_usage();
+            exit(1);
+        }
+        "--udp" => {
+            transport = Transport::Udp(args[1].clone());
+            args = &args[2..];
+        }
+        "--tcp" => {
+            transport = Transport::Tcp(args[1].clone());
+            args = &args[2..];
+        }
+        _ => {}
+    }
+
+    if args.is_empty() {
+        print_usage();
+        exit(1);
+    }
+
+    let result = match args[0].as_str() {
         "list" => cmd_list(),
 
-        "probe" => cmd_probe(),
+        "probe" => open_bridge(&transport).and_then(|b| cmd_probe(&b)),
 
         "read" => {
-            if args.len() < 3 {
+            let (args, fifo) = strip_fifo_flag(&args[1..]);
+            if args.is_empty() {
                 eprintln!("Error: read requires an address");
                 print_usage();
                 exit(1);
             }
-            let addr = match parse_u32(&args[2]) {
+            let addr = match parse_u32(&args[0]) {
                 Ok(a) => a,
                 Err(e) => {
                     eprintln!("Error: invalid address: {}", e);
                     exit(1);
                 }
             };
-            let count = if args.len() > 3 {
-                match args[3].parse::<usize>() {
+            let count = if args.len() > 1 {
+                match args[1].parse::<usize>() {
                     Ok(c) => c,
                     Err(e) => {
                         eprintln!("Error: invalid count: {}", e);
@@ -163,53 +334,126 @@ fn main() {
             } else {
                 1
             };
-            cmd_read(addr, count)
+            open_bridge(&transport).and_then(|b| cmd_read(&b, addr, count, fifo))
         }
 
         "write" => {
-            if args.len() < 4 {
+            let (args, fifo) = strip_fifo_flag(&args[1..]);
+            if args.len() < 2 {
                 eprintln!("Error: write requires address and value");
                 print_usage();
                 exit(1);
             }
-            let addr = match parse_u32(&args[2]) {
+            let addr = match parse_u32(&args[0]) {
                 Ok(a) => a,
                 Err(e) => {
                     eprintln!("Error: invalid address: {}", e);
                     exit(1);
                 }
             };
-            let value = match parse_u32(&args[3]) {
+            let value = match parse_u32(&args[1]) {
                 Ok(v) => v,
                 Err(e) => {
                     eprintln!("Error: invalid value: {}", e);
                     exit(1);
                 }
             };
-            cmd_write(addr, value)
+            open_bridge(&transport).and_then(|b| cmd_write(&b, addr, value, fifo))
         }
 
         "dump" => {
-            if args.len() < 4 {
+            let (args, fifo) = strip_fifo_flag(&args[1..]);
+            if args.len() < 2 {
                 eprintln!("Error: dump requires address and count");
                 print_usage();
                 exit(1);
             }
-            let addr = match parse_u32(&args[2]) {
+            let addr = match parse_u32(&args[0]) {
                 Ok(a) => a,
                 Err(e) => {
                     eprintln!("Error: invalid address: {}", e);
                     exit(1);
                 }
             };
-            let count = match args[3].parse::<usize>() {
+            let count = match args[1].parse::<usize>() {
                 Ok(c) => c,
                 Err(e) => {
                     eprintln!("Error: invalid count: {}", e);
                     exit(1);
                 }
             };
-            cmd_dump(addr, count)
+            open_bridge(&transport).and_then(|b| cmd_dump(&b, addr, count, fifo))
+        }
+
+        "enum" => {
+            if args.len() < 2 {
+                eprintln!("Error: enum requires an SDB ROM address");
+                print_usage();
+                exit(1);
+            }
+            let sdb_addr = match parse_u32(&args[1]) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("Error: invalid address: {}", e);
+                    exit(1);
+                }
+            };
+            open_bridge(&transport).and_then(|b| cmd_enum(&b, sdb_addr))
+        }
+
+        "load" => {
+            if args.len() < 3 {
+                eprintln!("Error: load requires an address and a file");
+                print_usage();
+                exit(1);
+            }
+            let addr = match parse_u32(&args[1]) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("Error: invalid address: {}", e);
+                    exit(1);
+                }
+            };
+            open_bridge(&transport).and_then(|b| cmd_load(&b, addr, &args[2]))
+        }
+
+        "save" => {
+            if args.len() < 4 {
+                eprintln!("Error: save requires an address, count and a file");
+                print_usage();
+                exit(1);
+            }
+            let addr = match parse_u32(&args[1]) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("Error: invalid address: {}", e);
+                    exit(1);
+                }
+            };
+            let count = match args[2].parse::<usize>() {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("Error: invalid count: {}", e);
+                    exit(1);
+                }
+            };
+            open_bridge(&transport).and_then(|b| cmd_save(&b, addr, count, &args[3]))
+        }
+
+        "verify" => {
+            if args.len() < 3 {
+                eprintln!("Error: verify requires an address and a file");
+                print_usage();
+                exit(1);
+            }
+            let addr = match parse_u32(&args[1]) {
+                Ok(a) => a,
+                Err(e) => {
+                    eprintln!("Error: invalid address: {}", e);
+                    exit(1);
+                }
+            };
+            open_bridge(&transport).and_then(|b| cmd_verify(&b, addr, &args[2]))
         }
 
         "help" | "-h" | "--help" => {
@@ -78,50 +78,94 @@ fn handle_client(
 
         let device = device.lock().unwrap();
 
-        // Handle writes
-        if let Some((base_addr, data)) = &packet.writes {
-            debug!("Write {} values @ 0x{:08x}", data.len(), base_addr);
-            let write_packet = etherbone::Packet::write_burst(*base_addr, data.clone());
-            if let Err(e) = device.send(0, &write_packet.encode()) {
-                error!("Write error: {}", e);
+        // Process each record independently, same as a real Etherbone
+        // fabric would
+        for record in &packet.records {
+            // Handle writes. A wff-flagged record must be relayed so the
+            // device holds the address constant too, or a FIFO write from
+            // the remote client turns into a scattered auto-incrementing
+            // burst on the FT601 side.
+            if let Some((base_addr, data)) = &record.writes {
+                debug!(
+                    "Write {} values @ 0x{:08x}{}",
+                    data.len(),
+                    base_addr,
+                    if record.wff() { " (fifo)" } else { "" }
+                );
+                let write_packet = if record.wff() {
+                    etherbone::Packet::write_fifo(*base_addr, data.clone())
+                } else {
+                    etherbone::Packet::write_burst(*base_addr, data.clone())
+                };
+                if let Err(e) = device.send(0, &write_packet.encode()) {
+                    error!("Write error: {}", e);
+                }
             }
-        }
 
-        // Handle reads
-        if let Some((base_ret_addr, addrs)) = &packet.reads {
-            debug!("Read {} addresses", addrs.len());
-
-            let mut results = Vec::new();
-            for &addr in addrs {
-                let read_packet = etherbone::Packet::read(addr);
-                match device.transact(0, &read_packet.encode(), 100) {
-                    Ok(response) => {
-                        if let Some(resp) = etherbone::Packet::decode(&response) {
-                            if let Some(data) = resp.get_read_data() {
-                                if let Some(&val) = data.first() {
-                                    debug!("  0x{:08x} -> 0x{:08x}", addr, val);
-                                    results.push(val);
-                                    continue;
+            // Handle reads. Likewise, an rff-flagged record must be relayed
+            // as a single read-FIFO request so the device holds the
+            // address constant, rather than as independent single reads.
+            if let Some((base_ret_addr, addrs)) = &record.reads {
+                debug!(
+                    "Read {} addresses{}",
+                    addrs.len(),
+                    if record.rff() { " (fifo)" } else { "" }
+                );
+
+                let results = if record.rff() {
+                    let addr = addrs.first().copied().unwrap_or(0);
+                    let read_packet = etherbone::Packet::read_fifo(addr, addrs.len());
+                    match device.transact(0, &read_packet.encode(), 100) {
+                        Ok(response) => etherbone::Packet::decode(&response)
+                            .and_then(|resp| resp.get_read_data().map(|d| d.to_vec()))
+                            .unwrap_or_else(|| {
+                                warn!("Invalid fifo response for read @ 0x{:08x}", addr);
+                                vec![0xffffffff; addrs.len()]
+                            }),
+                        Err(Error::Timeout) => {
+                            warn!("Timeout reading fifo @ 0x{:08x}", addr);
+                            vec![0xffffffff; addrs.len()]
+                        }
+                        Err(e) => {
+                            error!("Read error @ 0x{:08x}: {}", addr, e);
+                            vec![0xffffffff; addrs.len()]
+                        }
+                    }
+                } else {
+                    let mut results = Vec::new();
+                    for &addr in addrs {
+                        let read_packet = etherbone::Packet::read(addr);
+                        match device.transact(0, &read_packet.encode(), 100) {
+                            Ok(response) => {
+                                if let Some(resp) = etherbone::Packet::decode(&response) {
+                                    if let Some(data) = resp.get_read_data() {
+                                        if let Some(&val) = data.first() {
+                                            debug!("  0x{:08x} -> 0x{:08x}", addr, val);
+                                            results.push(val);
+                                            continue;
+                                        }
+                                    }
                                 }
+                                warn!("Invalid response for read @ 0x{:08x}", addr);
+                                results.push(0xffffffff);
+                            }
+                            Err(Error::Timeout) => {
+                                warn!("Timeout reading @ 0x{:08x}", addr);
+                                results.push(0xffffffff);
+                            }
+                            Err(e) => {
+                                error!("Read error @ 0x{:08x}: {}", addr, e);
+                                results.push(0xffffffff);
                             }
                         }
-                        warn!("Invalid response for read @ 0x{:08x}", addr);
-                        results.push(0xffffffff);
-                    }
-                    Err(Error::Timeout) => {
-                        warn!("Timeout reading @ 0x{:08x}", addr);
-                        results.push(0xffffffff);
-                    }
-                    Err(e) => {
-                        error!("Read error @ 0x{:08x}: {}", addr, e);
-                        results.push(0xffffffff);
                     }
-                }
-            }
+                    results
+                };
 
-            // Send response
-            let response = etherbone::Packet::read_response(*base_ret_addr, results);
-            stream.write_all(&response.encode())?;
+                // Send response
+                let response = etherbone::Packet::read_response(*base_ret_addr, results);
+                stream.write_all(&response.encode())?;
+            }
         }
     }
 
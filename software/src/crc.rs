@@ -0,0 +1,90 @@
+//! CRC-32 framing for `Bridge::reliable` mode
+//!
+//! Wraps an encoded Etherbone packet as `[u16 len][payload][u32 crc32]` so
+//! a corrupted or truncated USB bulk transfer can be detected and retried
+//! instead of silently decoding into wrong register data.
+
+/// Standard CRC-32 (IEEE 802.3 polynomial, reflected) - the same variant
+/// zlib and Ethernet use.
+const POLY: u32 = 0xedb8_8320;
+
+/// Compute the CRC-32 of `data`
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Wrap `payload` in a `[u16 len][payload][u32 crc32]` frame. Fails if
+/// `payload` is too long for the `u16` length prefix to represent.
+pub fn frame(payload: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if payload.len() > u16::MAX as usize {
+        return Err("payload too long to frame");
+    }
+    let mut buf = Vec::with_capacity(2 + payload.len() + 4);
+    buf.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+    buf.extend_from_slice(payload);
+    buf.extend_from_slice(&crc32(payload).to_be_bytes());
+    Ok(buf)
+}
+
+/// Validate and strip a `[u16 len][payload][u32 crc32]` frame, returning
+/// the payload. Fails on a short/truncated frame or a CRC mismatch.
+pub fn unframe(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if data.len() < 6 {
+        return Err("frame too short");
+    }
+    let len = u16::from_be_bytes([data[0], data[1]]) as usize;
+    if data.len() < 2 + len + 4 {
+        return Err("frame length mismatch");
+    }
+
+    let payload = &data[2..2 + len];
+    let expected = u32::from_be_bytes(data[2 + len..2 + len + 4].try_into().unwrap());
+    if crc32(payload) != expected {
+        return Err("CRC mismatch");
+    }
+
+    Ok(payload.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_vector() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let payload = vec![0xde, 0xad, 0xbe, 0xef];
+        let framed = frame(&payload).unwrap();
+        assert_eq!(unframe(&framed).unwrap(), payload);
+    }
+
+    #[test]
+    fn test_unframe_detects_corruption() {
+        let payload = vec![0xde, 0xad, 0xbe, 0xef];
+        let mut framed = frame(&payload).unwrap();
+        let last = framed.len() - 1;
+        framed[last] ^= 0xff;
+        assert_eq!(unframe(&framed), Err("CRC mismatch"));
+    }
+
+    #[test]
+    fn test_frame_rejects_oversized_payload() {
+        let payload = vec![0u8; u16::MAX as usize + 1];
+        assert_eq!(frame(&payload), Err("payload too long to frame"));
+    }
+}
@@ -12,6 +12,157 @@ pub const PACKET_HEADER_LEN: usize = 8;
 /// Record header length
 pub const RECORD_HEADER_LEN: usize = 4;
 
+/// Record flag: bus cycle address - base write address is a Wishbone
+/// bus address rather than a config-space offset
+pub const FLAG_BCA: u8 = 0x80;
+/// Record flag: read cycle address - base read address is a Wishbone
+/// bus address rather than a config-space offset
+pub const FLAG_RCA: u8 = 0x40;
+/// Record flag: read-FIFO - hold the read address constant across the
+/// whole read block instead of auto-incrementing
+pub const FLAG_RFF: u8 = 0x20;
+/// Record flag: config-space cycle
+pub const FLAG_CYC: u8 = 0x10;
+/// Record flag: write cycle address - base write address is a Wishbone
+/// bus address rather than a config-space offset
+pub const FLAG_WCA: u8 = 0x08;
+/// Record flag: write-FIFO - hold the write address constant across the
+/// whole write block instead of auto-incrementing
+pub const FLAG_WFF: u8 = 0x04;
+
+/// A single Etherbone record: one write block and/or one read block,
+/// each carrying its own flags and byte-enable mask. A packet is a
+/// sequence of these back-to-back after the packet header.
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// Record flags (bca, rca, rff, cyc, wca, wff)
+    pub flags: u8,
+    /// Byte-enable mask
+    pub byte_enable: u8,
+    /// Write operations: (base_addr, data_values)
+    pub writes: Option<(u32, Vec<u32>)>,
+    /// Read operations: (base_ret_addr, addresses)
+    pub reads: Option<(u32, Vec<u32>)>,
+}
+
+impl Record {
+    /// Create a new empty record
+    pub fn new() -> Self {
+        Record {
+            flags: 0x00,
+            byte_enable: 0x0f,
+            writes: None,
+            reads: None,
+        }
+    }
+
+    /// Create a write record
+    pub fn write(addr: u32, data: u32) -> Self {
+        Record {
+            writes: Some((addr, vec![data])),
+            ..Record::new()
+        }
+    }
+
+    /// Create a multi-write record
+    pub fn write_burst(base_addr: u32, data: Vec<u32>) -> Self {
+        Record {
+            writes: Some((base_addr, data)),
+            ..Record::new()
+        }
+    }
+
+    /// Create a read record
+    pub fn read(addr: u32) -> Self {
+        Record {
+            reads: Some((0, vec![addr])),
+            ..Record::new()
+        }
+    }
+
+    /// Create a multi-read record
+    pub fn read_burst(addrs: Vec<u32>) -> Self {
+        Record {
+            reads: Some((0, addrs)),
+            ..Record::new()
+        }
+    }
+
+    /// Create a read response record (data returned as writes)
+    pub fn read_response(base_ret_addr: u32, data: Vec<u32>) -> Self {
+        Record {
+            writes: Some((base_ret_addr, data)),
+            ..Record::new()
+        }
+    }
+
+    /// Create a write-FIFO record: `addr` is held constant across the
+    /// whole write block instead of auto-incrementing
+    pub fn write_fifo(addr: u32, data: Vec<u32>) -> Self {
+        Record {
+            flags: FLAG_WFF,
+            writes: Some((addr, data)),
+            ..Record::new()
+        }
+    }
+
+    /// Create a read-FIFO record: `addr` is held constant across the
+    /// whole read block instead of auto-incrementing
+    pub fn read_fifo(addr: u32, count: usize) -> Self {
+        Record {
+            flags: FLAG_RFF,
+            reads: Some((0, vec![addr; count])),
+            ..Record::new()
+        }
+    }
+
+    /// Is the read-FIFO flag set on this record?
+    pub fn rff(&self) -> bool {
+        self.flags & FLAG_RFF != 0
+    }
+
+    /// Is the write-FIFO flag set on this record?
+    pub fn wff(&self) -> bool {
+        self.flags & FLAG_WFF != 0
+    }
+
+    /// Is the config-space cycle flag set on this record?
+    pub fn cyc(&self) -> bool {
+        self.flags & FLAG_CYC != 0
+    }
+
+    /// Encode this record's header and payload, appending to `buf`
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let wcount = self.writes.as_ref().map_or(0, |(_, d)| d.len() as u8);
+        let rcount = self.reads.as_ref().map_or(0, |(_, a)| a.len() as u8);
+
+        buf.push(self.flags);
+        buf.push(self.byte_enable);
+        buf.push(wcount);
+        buf.push(rcount);
+
+        if let Some((base_addr, data)) = &self.writes {
+            buf.extend_from_slice(&base_addr.to_be_bytes());
+            for &val in data {
+                buf.extend_from_slice(&val.to_be_bytes());
+            }
+        }
+
+        if let Some((base_ret_addr, addrs)) = &self.reads {
+            buf.extend_from_slice(&base_ret_addr.to_be_bytes());
+            for &addr in addrs {
+                buf.extend_from_slice(&addr.to_be_bytes());
+            }
+        }
+    }
+}
+
+impl Default for Record {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Etherbone packet
 #[derive(Debug, Clone)]
 pub struct Packet {
@@ -19,10 +170,8 @@ pub struct Packet {
     pub probe: bool,
     /// Probe reply flag
     pub probe_reply: bool,
-    /// Write operations: (base_addr, data_values)
-    pub writes: Option<(u32, Vec<u32>)>,
-    /// Read operations: (base_ret_addr, addresses)
-    pub reads: Option<(u32, Vec<u32>)>,
+    /// Records carried by this packet, in wire order
+    pub records: Vec<Record>,
 }
 
 impl Packet {
@@ -31,8 +180,7 @@ impl Packet {
         Packet {
             probe: false,
             probe_reply: false,
-            writes: None,
-            reads: None,
+            records: Vec::new(),
         }
     }
 
@@ -40,69 +188,71 @@ impl Packet {
     pub fn probe_request() -> Self {
         Packet {
             probe: true,
-            probe_reply: false,
-            writes: None,
-            reads: None,
+            ..Packet::new()
         }
     }
 
     /// Create a probe reply packet
     pub fn probe_reply() -> Self {
         Packet {
-            probe: false,
             probe_reply: true,
-            writes: None,
-            reads: None,
+            ..Packet::new()
         }
     }
 
-    /// Create a write packet
+    /// Create a single-record write packet
     pub fn write(addr: u32, data: u32) -> Self {
         Packet {
-            probe: false,
-            probe_reply: false,
-            writes: Some((addr, vec![data])),
-            reads: None,
+            records: vec![Record::write(addr, data)],
+            ..Packet::new()
         }
     }
 
-    /// Create a multi-write packet
+    /// Create a single-record multi-write packet
     pub fn write_burst(base_addr: u32, data: Vec<u32>) -> Self {
         Packet {
-            probe: false,
-            probe_reply: false,
-            writes: Some((base_addr, data)),
-            reads: None,
+            records: vec![Record::write_burst(base_addr, data)],
+            ..Packet::new()
         }
     }
 
-    /// Create a read request packet
+    /// Create a single-record read request packet
     pub fn read(addr: u32) -> Self {
         Packet {
-            probe: false,
-            probe_reply: false,
-            writes: None,
-            reads: Some((0, vec![addr])),
+            records: vec![Record::read(addr)],
+            ..Packet::new()
         }
     }
 
-    /// Create a multi-read request packet
+    /// Create a single-record multi-read request packet
     pub fn read_burst(addrs: Vec<u32>) -> Self {
         Packet {
-            probe: false,
-            probe_reply: false,
-            writes: None,
-            reads: Some((0, addrs)),
+            records: vec![Record::read_burst(addrs)],
+            ..Packet::new()
         }
     }
 
-    /// Create a read response packet (data returned as writes)
+    /// Create a single-record read response packet (data returned as writes)
     pub fn read_response(base_ret_addr: u32, data: Vec<u32>) -> Self {
         Packet {
-            probe: false,
-            probe_reply: false,
-            writes: Some((base_ret_addr, data)),
-            reads: None,
+            records: vec![Record::read_response(base_ret_addr, data)],
+            ..Packet::new()
+        }
+    }
+
+    /// Create a single-record write-FIFO packet (address held constant)
+    pub fn write_fifo(addr: u32, data: Vec<u32>) -> Self {
+        Packet {
+            records: vec![Record::write_fifo(addr, data)],
+            ..Packet::new()
+        }
+    }
+
+    /// Create a single-record read-FIFO packet (address held constant)
+    pub fn read_fifo(addr: u32, count: usize) -> Self {
+        Packet {
+            records: vec![Record::read_fifo(addr, count)],
+            ..Packet::new()
         }
     }
 
@@ -126,36 +276,13 @@ impl Packet {
         // Padding to 8 bytes
         buf.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
 
-        // If probe-only, no record needed
-        if self.probe || self.probe_reply {
-            if self.writes.is_none() && self.reads.is_none() {
-                return buf;
-            }
+        // Probe-only packets carry no records
+        if (self.probe || self.probe_reply) && self.records.is_empty() {
+            return buf;
         }
 
-        // Record header (4 bytes)
-        let wcount = self.writes.as_ref().map_or(0, |(_, d)| d.len() as u8);
-        let rcount = self.reads.as_ref().map_or(0, |(_, a)| a.len() as u8);
-
-        buf.push(0x00); // flags (bca, rca, rff, cyc, wca, wff)
-        buf.push(0x0f); // byte_enable
-        buf.push(wcount);
-        buf.push(rcount);
-
-        // Writes section
-        if let Some((base_addr, data)) = &self.writes {
-            buf.extend_from_slice(&base_addr.to_be_bytes());
-            for &val in data {
-                buf.extend_from_slice(&val.to_be_bytes());
-            }
-        }
-
-        // Reads section
-        if let Some((base_ret_addr, addrs)) = &self.reads {
-            buf.extend_from_slice(&base_ret_addr.to_be_bytes());
-            for &addr in addrs {
-                buf.extend_from_slice(&addr.to_be_bytes());
-            }
+        for record in &self.records {
+            record.encode(&mut buf);
         }
 
         buf
@@ -177,95 +304,118 @@ impl Packet {
         let probe_reply = (flags & 0x02) != 0;
         let probe = (flags & 0x01) != 0;
 
-        // Probe-only packets have no record
+        // Probe-only packets have no records
         if data.len() == PACKET_HEADER_LEN {
             return Some(Packet {
                 probe,
                 probe_reply,
-                writes: None,
-                reads: None,
+                records: Vec::new(),
             });
         }
 
+        // Anything beyond the header must be a full record stream: at
+        // least one record header. A truncated buffer in between (e.g.
+        // a short read) is a decode failure, not a zero-record packet.
         if data.len() < PACKET_HEADER_LEN + RECORD_HEADER_LEN {
             return None;
         }
 
-        // Record header
-        let wcount = data[10] as usize;
-        let rcount = data[11] as usize;
+        let mut records = Vec::new();
+        let mut offset = PACKET_HEADER_LEN;
 
-        let mut offset = PACKET_HEADER_LEN + RECORD_HEADER_LEN;
+        while offset + RECORD_HEADER_LEN <= data.len() {
+            let record_flags = data[offset];
+            let byte_enable = data[offset + 1];
+            let wcount = data[offset + 2] as usize;
+            let rcount = data[offset + 3] as usize;
 
-        // Parse writes
-        let writes = if wcount > 0 {
-            if data.len() < offset + 4 + (wcount * 4) {
-                return None;
+            // A zero-count header marks the end of the record stream
+            // (e.g. trailing padding)
+            if wcount == 0 && rcount == 0 {
+                break;
             }
-            let base_addr = u32::from_be_bytes([
-                data[offset],
-                data[offset + 1],
-                data[offset + 2],
-                data[offset + 3],
-            ]);
-            offset += 4;
-
-            let mut values = Vec::with_capacity(wcount);
-            for _ in 0..wcount {
-                let val = u32::from_be_bytes([
+
+            offset += RECORD_HEADER_LEN;
+
+            let writes = if wcount > 0 {
+                if data.len() < offset + 4 + (wcount * 4) {
+                    return None;
+                }
+                let base_addr = u32::from_be_bytes([
                     data[offset],
                     data[offset + 1],
                     data[offset + 2],
                     data[offset + 3],
                 ]);
-                values.push(val);
                 offset += 4;
-            }
-            Some((base_addr, values))
-        } else {
-            None
-        };
 
-        // Parse reads
-        let reads = if rcount > 0 {
-            if data.len() < offset + 4 + (rcount * 4) {
-                return None;
-            }
-            let base_ret_addr = u32::from_be_bytes([
-                data[offset],
-                data[offset + 1],
-                data[offset + 2],
-                data[offset + 3],
-            ]);
-            offset += 4;
-
-            let mut addrs = Vec::with_capacity(rcount);
-            for _ in 0..rcount {
-                let addr = u32::from_be_bytes([
+                let mut values = Vec::with_capacity(wcount);
+                for _ in 0..wcount {
+                    let val = u32::from_be_bytes([
+                        data[offset],
+                        data[offset + 1],
+                        data[offset + 2],
+                        data[offset + 3],
+                    ]);
+                    values.push(val);
+                    offset += 4;
+                }
+                Some((base_addr, values))
+            } else {
+                None
+            };
+
+            let reads = if rcount > 0 {
+                if data.len() < offset + 4 + (rcount * 4) {
+                    return None;
+                }
+                let base_ret_addr = u32::from_be_bytes([
                     data[offset],
                     data[offset + 1],
                     data[offset + 2],
                     data[offset + 3],
                 ]);
-                addrs.push(addr);
                 offset += 4;
-            }
-            Some((base_ret_addr, addrs))
-        } else {
-            None
-        };
+
+                let mut addrs = Vec::with_capacity(rcount);
+                for _ in 0..rcount {
+                    let addr = u32::from_be_bytes([
+                        data[offset],
+                        data[offset + 1],
+                        data[offset + 2],
+                        data[offset + 3],
+                    ]);
+                    addrs.push(addr);
+                    offset += 4;
+                }
+                Some((base_ret_addr, addrs))
+            } else {
+                None
+            };
+
+            records.push(Record {
+                flags: record_flags,
+                byte_enable,
+                writes,
+                reads,
+            });
+        }
 
         Some(Packet {
             probe,
             probe_reply,
-            writes,
-            reads,
+            records,
         })
     }
 
-    /// Get write data if this is a read response
+    /// Write data of the first record that carries a write block, if any -
+    /// the common case for a read response, which packages the returned
+    /// words as a write block
     pub fn get_read_data(&self) -> Option<&[u32]> {
-        self.writes.as_ref().map(|(_, data)| data.as_slice())
+        self.records
+            .iter()
+            .find_map(|r| r.writes.as_ref())
+            .map(|(_, data)| data.as_slice())
     }
 }
 
@@ -285,7 +435,7 @@ mod tests {
         let encoded = packet.encode();
         let decoded = Packet::decode(&encoded).unwrap();
 
-        let (addr, data) = decoded.writes.unwrap();
+        let (addr, data) = decoded.records[0].writes.clone().unwrap();
         assert_eq!(addr, 0x12345678);
         assert_eq!(data, vec![0xdeadbeef]);
     }
@@ -296,7 +446,7 @@ mod tests {
         let encoded = packet.encode();
         let decoded = Packet::decode(&encoded).unwrap();
 
-        let (_, addrs) = decoded.reads.unwrap();
+        let (_, addrs) = decoded.records[0].reads.clone().unwrap();
         assert_eq!(addrs, vec![0x12345678]);
     }
 
@@ -307,4 +457,49 @@ mod tests {
         let decoded = Packet::decode(&encoded).unwrap();
         assert!(decoded.probe);
     }
+
+    #[test]
+    fn test_decode_truncated_packet_fails() {
+        // Header plus 1-3 trailing bytes: too short to be either a
+        // probe-only packet or a complete record header.
+        let mut data = vec![0x4e, 0x6f, 0x01, 0x00, 0, 0, 0, 0];
+        data.push(0xaa);
+        assert!(Packet::decode(&data).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_fifo() {
+        let write_packet = Packet::write_fifo(0x1000, vec![1, 2, 3]);
+        let decoded = Packet::decode(&write_packet.encode()).unwrap();
+        assert!(decoded.records[0].wff());
+        assert_eq!(decoded.records[0].writes.clone().unwrap().1, vec![1, 2, 3]);
+
+        let read_packet = Packet::read_fifo(0x2000, 4);
+        let decoded = Packet::decode(&read_packet.encode()).unwrap();
+        assert!(decoded.records[0].rff());
+        assert_eq!(
+            decoded.records[0].reads.clone().unwrap().1,
+            vec![0x2000; 4]
+        );
+    }
+
+    #[test]
+    fn test_encode_decode_multi_record() {
+        let packet = Packet {
+            records: vec![
+                Record::write(0x1000, 0xaaaa_aaaa),
+                Record::read_burst(vec![0x2000, 0x2004]),
+            ],
+            ..Packet::new()
+        };
+        let encoded = packet.encode();
+        let decoded = Packet::decode(&encoded).unwrap();
+
+        assert_eq!(decoded.records.len(), 2);
+        let (addr, data) = decoded.records[0].writes.clone().unwrap();
+        assert_eq!(addr, 0x1000);
+        assert_eq!(data, vec![0xaaaa_aaaa]);
+        let (_, addrs) = decoded.records[1].reads.clone().unwrap();
+        assert_eq!(addrs, vec![0x2000, 0x2004]);
+    }
 }
@@ -0,0 +1,165 @@
+//! File-oriented memory image operations
+//!
+//! `load`/`save`/`verify` move whole binary images (firmware, bitstreams,
+//! memory dumps) to and from device memory, splitting large transfers into
+//! multiple Etherbone packets of at most `Bridge::max_burst_words` words.
+
+use crate::{Bridge, Error};
+
+/// Pack bytes into little-endian 32-bit words, zero-padding a trailing
+/// partial word
+fn bytes_to_words(data: &[u8]) -> Vec<u32> {
+    data.chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        })
+        .collect()
+}
+
+impl Bridge {
+    /// Write `data` into device memory starting at `addr`, packing it into
+    /// little-endian 32-bit words (a trailing partial word is zero-padded)
+    /// and splitting the transfer into chunks of at most `max_burst_words`
+    /// words so large images don't exceed a single Etherbone packet.
+    pub fn load(&self, addr: u32, data: &[u8]) -> Result<(), Error> {
+        let words = bytes_to_words(data);
+        let chunk_words = self.max_burst_words.max(1);
+
+        for (i, chunk) in words.chunks(chunk_words).enumerate() {
+            let base = addr + (i * chunk_words * 4) as u32;
+            self.write_burst(base, chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Read `count` 32-bit words from device memory starting at `addr`,
+    /// returning their raw little-endian bytes. Reads are split the same
+    /// way `load` splits writes.
+    pub fn save(&self, addr: u32, count: usize) -> Result<Vec<u8>, Error> {
+        let chunk_words = self.max_burst_words.max(1);
+        let mut bytes = Vec::with_capacity(count * 4);
+        let mut remaining = count;
+        let mut cur_addr = addr;
+
+        while remaining > 0 {
+            let n = remaining.min(chunk_words);
+            let addrs: Vec<u32> = (0..n as u32).map(|i| cur_addr + i * 4).collect();
+            for value in self.read_burst(&addrs)? {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            cur_addr += (n * 4) as u32;
+            remaining -= n;
+        }
+        Ok(bytes)
+    }
+
+    /// Write `data` to `addr`, read it back, and return the offset of the
+    /// first mismatching byte, or `None` if the readback matches exactly
+    pub fn verify(&self, addr: u32, data: &[u8]) -> Result<Option<usize>, Error> {
+        self.load(addr, data)?;
+
+        let word_count = (data.len() + 3) / 4;
+        let readback = self.save(addr, word_count)?;
+
+        Ok(data
+            .iter()
+            .zip(readback.iter())
+            .position(|(a, b)| a != b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etherbone::{Packet, Record};
+    use crate::transport::mock::MockTransport;
+    use crate::Transport;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn bridge_with_burst_limit(max_burst_words: usize) -> Bridge {
+        let mut bridge = Bridge::with_transport(Box::new(MockTransport::new()));
+        bridge.max_burst_words = max_burst_words;
+        bridge
+    }
+
+    #[test]
+    fn test_bytes_to_words_zero_pads_trailing_partial_word() {
+        let words = bytes_to_words(&[0x01, 0x02, 0x03]);
+        assert_eq!(words, vec![u32::from_le_bytes([0x01, 0x02, 0x03, 0x00])]);
+    }
+
+    #[test]
+    fn test_load_save_roundtrip_across_multiple_chunks() {
+        // 10 words of data with a 4-word chunk limit: exercises the
+        // chunking math across three write_burst/read_burst calls.
+        let bridge = bridge_with_burst_limit(4);
+        let data: Vec<u8> = (0..40u8).collect();
+
+        bridge.load(0x1000, &data).unwrap();
+        let readback = bridge.save(0x1000, 10).unwrap();
+
+        assert_eq!(readback, data);
+    }
+
+    #[test]
+    fn test_verify_reports_none_on_exact_match() {
+        let bridge = bridge_with_burst_limit(4);
+        let data: Vec<u8> = (0..40u8).collect();
+
+        assert_eq!(bridge.verify(0x1000, &data).unwrap(), None);
+    }
+
+    /// Like [`MockTransport`], but one fixed address silently keeps
+    /// whatever it already held instead of accepting a write -
+    /// simulating a stuck register so `verify`'s readback diverges
+    /// from what was written.
+    struct StuckAddrTransport {
+        memory: Mutex<HashMap<u32, u32>>,
+        stuck_addr: u32,
+    }
+
+    impl Transport for StuckAddrTransport {
+        fn transact(&self, _channel: u8, req: &[u8], _timeout_ms: u32) -> Result<Vec<u8>, Error> {
+            let packet = Packet::decode(req).ok_or_else(|| Error::Protocol("bad request".into()))?;
+            let mut memory = self.memory.lock().unwrap();
+            let mut response = Packet::new();
+
+            for record in &packet.records {
+                if let Some((base_addr, data)) = &record.writes {
+                    for (i, &val) in data.iter().enumerate() {
+                        let addr = base_addr + (i as u32) * 4;
+                        if addr != self.stuck_addr {
+                            memory.insert(addr, val);
+                        }
+                    }
+                }
+                if let Some((base_ret_addr, addrs)) = &record.reads {
+                    let values: Vec<u32> =
+                        addrs.iter().map(|a| *memory.get(a).unwrap_or(&0)).collect();
+                    response.records.push(Record::read_response(*base_ret_addr, values));
+                }
+            }
+            Ok(response.encode())
+        }
+
+        fn send(&self, channel: u8, req: &[u8]) -> Result<(), Error> {
+            self.transact(channel, req, 0).map(|_| ())
+        }
+    }
+
+    #[test]
+    fn test_verify_reports_offset_of_first_mismatch() {
+        let stuck = StuckAddrTransport {
+            memory: Mutex::new(HashMap::new()),
+            stuck_addr: 0x1000 + 8, // word 2, byte offset 8
+        };
+        let mut bridge = Bridge::with_transport(Box::new(stuck));
+        bridge.max_burst_words = 4;
+        let data: Vec<u8> = (0..40u8).collect();
+
+        assert_eq!(bridge.verify(0x1000, &data).unwrap(), Some(8));
+    }
+}
@@ -18,11 +18,17 @@
 //! bridge.write(0x12345678, 0xdeadbeef).unwrap();
 //! ```
 
+pub mod crc;
 pub mod etherbone;
+pub mod image;
+pub mod sdb;
+pub mod transport;
 pub mod usb;
 
 use std::sync::Mutex;
 
+pub use transport::Transport;
+
 /// Library error types
 #[derive(Debug)]
 pub enum Error {
@@ -49,37 +55,103 @@ impl std::fmt::Display for Error {
 
 impl std::error::Error for Error {}
 
-/// High-level bridge for Wishbone register access via FT601
+/// High-level bridge for Wishbone register access over any Etherbone
+/// [`Transport`] - FT601 USB, UDP, or TCP.
 pub struct Bridge {
-    device: Mutex<usb::Device>,
-    /// USB channel for Etherbone (default: 0)
+    transport: Mutex<Box<dyn Transport + Send>>,
+    /// Etherbone channel (meaningful for the FT601 transport only, default: 0)
     pub channel: u8,
     /// Timeout in milliseconds for read operations
     pub timeout_ms: u32,
+    /// When set, every packet is wrapped in a CRC-32 frame (see [`crc`])
+    /// and the receive path retries on a corrupted/truncated transfer
+    /// instead of handing bad data to `Packet::decode`. Off by default.
+    pub reliable: bool,
+    /// Number of retries after a failed reliable transaction before
+    /// giving up. Only consulted when `reliable` is set.
+    pub retries: u8,
+    /// Maximum number of 32-bit words per `read_burst`/`write_burst`
+    /// transaction issued by [`Bridge::load`]/[`Bridge::save`]/
+    /// [`Bridge::verify`]. Larger images are split across multiple
+    /// Etherbone packets automatically.
+    pub max_burst_words: usize,
 }
 
 impl Bridge {
+    pub(crate) fn with_transport(transport: Box<dyn Transport + Send>) -> Self {
+        Bridge {
+            transport: Mutex::new(transport),
+            channel: 0,
+            timeout_ms: 100,
+            reliable: false,
+            retries: 3,
+            max_burst_words: 255,
+        }
+    }
+
+    /// Send an encoded request, framing it if `reliable` is set
+    fn send_request(&self, request: &[u8]) -> Result<(), Error> {
+        let transport = self.transport.lock().unwrap();
+        if self.reliable {
+            let framed = crc::frame(request).map_err(|e| Error::Protocol(e.into()))?;
+            transport.send(self.channel, &framed)
+        } else {
+            transport.send(self.channel, request)
+        }
+    }
+
+    /// Send an encoded request and return the response, framing/validating
+    /// and retrying up to `retries` times if `reliable` is set
+    fn transact_request(&self, request: &[u8]) -> Result<Vec<u8>, Error> {
+        if !self.reliable {
+            let transport = self.transport.lock().unwrap();
+            return transport.transact(self.channel, request, self.timeout_ms);
+        }
+
+        let framed = crc::frame(request).map_err(|e| Error::Protocol(e.into()))?;
+        let mut last_err = Error::Protocol("CRC mismatch".into());
+
+        for _ in 0..=self.retries {
+            let attempt = {
+                let transport = self.transport.lock().unwrap();
+                transport.transact(self.channel, &framed, self.timeout_ms)
+            };
+            match attempt.and_then(|response| {
+                crc::unframe(&response).map_err(|e| Error::Protocol(e.to_string()))
+            }) {
+                Ok(payload) => return Ok(payload),
+                Err(e) => last_err = e,
+            }
+        }
+
+        Err(last_err)
+    }
+
     /// Open the first available FT601 device
     pub fn open() -> Result<Self, Error> {
         let device = usb::Device::open()?;
-        Ok(Bridge {
-            device: Mutex::new(device),
-            channel: 0,
-            timeout_ms: 100,
-        })
+        Ok(Bridge::with_transport(Box::new(device)))
     }
 
     /// Open a specific device by index
     pub fn open_by_index(index: usize) -> Result<Self, Error> {
         let device = usb::Device::open_by_index(index)?;
-        Ok(Bridge {
-            device: Mutex::new(device),
-            channel: 0,
-            timeout_ms: 100,
-        })
+        Ok(Bridge::with_transport(Box::new(device)))
+    }
+
+    /// Connect to a remote Etherbone host over UDP, e.g. "192.168.1.50:1234"
+    pub fn connect_udp(addr: &str) -> Result<Self, Error> {
+        let udp = transport::UdpTransport::connect(addr)?;
+        Ok(Bridge::with_transport(Box::new(udp)))
+    }
+
+    /// Connect to a remote Etherbone host over TCP, e.g. "192.168.1.50:1234"
+    pub fn connect_tcp(addr: &str) -> Result<Self, Error> {
+        let tcp = transport::TcpTransport::connect(addr)?;
+        Ok(Bridge::with_transport(Box::new(tcp)))
     }
 
-    /// List available devices
+    /// List available FT60x devices
     pub fn list_devices() -> Result<Vec<usb::DeviceInfo>, Error> {
         usb::Device::list()
     }
@@ -89,8 +161,7 @@ impl Bridge {
         let packet = etherbone::Packet::read(addr);
         let request = packet.encode();
 
-        let device = self.device.lock().unwrap();
-        let response = device.transact(self.channel, &request, self.timeout_ms)?;
+        let response = self.transact_request(&request)?;
 
         let resp_packet = etherbone::Packet::decode(&response)
             .ok_or_else(|| Error::Protocol("Invalid response packet".into()))?;
@@ -106,8 +177,7 @@ impl Bridge {
         let packet = etherbone::Packet::read_burst(addrs.to_vec());
         let request = packet.encode();
 
-        let device = self.device.lock().unwrap();
-        let response = device.transact(self.channel, &request, self.timeout_ms)?;
+        let response = self.transact_request(&request)?;
 
         let resp_packet = etherbone::Packet::decode(&response)
             .ok_or_else(|| Error::Protocol("Invalid response packet".into()))?;
@@ -121,19 +191,37 @@ impl Bridge {
     /// Write a 32-bit register
     pub fn write(&self, addr: u32, value: u32) -> Result<(), Error> {
         let packet = etherbone::Packet::write(addr, value);
-        let request = packet.encode();
-
-        let device = self.device.lock().unwrap();
-        device.send(self.channel, &request)
+        self.send_request(&packet.encode())
     }
 
     /// Write multiple 32-bit values starting at base address
     pub fn write_burst(&self, base_addr: u32, values: &[u32]) -> Result<(), Error> {
         let packet = etherbone::Packet::write_burst(base_addr, values.to_vec());
+        self.send_request(&packet.encode())
+    }
+
+    /// Read `count` words from a FIFO register at `addr`, holding the
+    /// address constant across the whole burst instead of auto-incrementing
+    pub fn read_fifo(&self, addr: u32, count: usize) -> Result<Vec<u32>, Error> {
+        let packet = etherbone::Packet::read_fifo(addr, count);
         let request = packet.encode();
 
-        let device = self.device.lock().unwrap();
-        device.send(self.channel, &request)
+        let response = self.transact_request(&request)?;
+
+        let resp_packet = etherbone::Packet::decode(&response)
+            .ok_or_else(|| Error::Protocol("Invalid response packet".into()))?;
+
+        resp_packet
+            .get_read_data()
+            .map(|d| d.to_vec())
+            .ok_or_else(|| Error::Protocol("No data in response".into()))
+    }
+
+    /// Write `data` to a FIFO register at `addr`, holding the address
+    /// constant across the whole burst instead of auto-incrementing
+    pub fn write_fifo(&self, addr: u32, data: &[u32]) -> Result<(), Error> {
+        let packet = etherbone::Packet::write_fifo(addr, data.to_vec());
+        self.send_request(&packet.encode())
     }
 
     /// Send a probe request and wait for reply
@@ -141,8 +229,7 @@ impl Bridge {
         let packet = etherbone::Packet::probe_request();
         let request = packet.encode();
 
-        let device = self.device.lock().unwrap();
-        match device.transact(self.channel, &request, self.timeout_ms) {
+        match self.transact_request(&request) {
             Ok(response) => {
                 if let Some(resp_packet) = etherbone::Packet::decode(&response) {
                     Ok(resp_packet.probe_reply)
@@ -158,4 +245,5 @@ impl Bridge {
 
 // Re-exports for convenience
 pub use etherbone::Packet as EtherbonePacket;
+pub use sdb::SdbDevice;
 pub use usb::Device as UsbDevice;
@@ -0,0 +1,177 @@
+//! SDB (Self-Describing Bus) ROM parsing
+//!
+//! Wishbone/Etherbone fabrics built with LiteX expose an SDB ROM
+//! describing every device on the bus, much like a PCI configuration
+//! space does for PCIe. This module walks that ROM via [`Bridge::enumerate`]
+//! so register maps can be discovered instead of hand-entered.
+
+use crate::{Bridge, Error};
+
+/// SDB magic number, the ASCII string "SDB-"
+pub const MAGIC: u32 = 0x5344_422d;
+/// Every SDB record, interconnect or device, is this many bytes
+pub const RECORD_LEN: usize = 64;
+/// `record_type` byte (offset 63) identifying a device record
+pub const RECORD_TYPE_DEVICE: u8 = 0x01;
+
+/// A device discovered by walking the SDB ROM
+#[derive(Debug, Clone)]
+pub struct SdbDevice {
+    /// ASCII product name
+    pub name: String,
+    /// 64-bit vendor ID
+    pub vendor_id: u64,
+    /// 32-bit device ID
+    pub device_id: u32,
+    /// First address of the device's range on the bus
+    pub addr_first: u64,
+    /// Last address of the device's range on the bus
+    pub addr_last: u64,
+}
+
+fn be_u64(b: &[u8]) -> u64 {
+    u64::from_be_bytes(b.try_into().unwrap())
+}
+
+fn be_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes(b.try_into().unwrap())
+}
+
+/// Parse a 64-byte device record (record_type == RECORD_TYPE_DEVICE) into
+/// an [`SdbDevice`]. The component fields (addr_first/addr_last/product)
+/// start at offset 8, same as in the interconnect header record.
+fn parse_device(record: &[u8; RECORD_LEN]) -> SdbDevice {
+    let addr_first = be_u64(&record[8..16]);
+    let addr_last = be_u64(&record[16..24]);
+    let vendor_id = be_u64(&record[24..32]);
+    let device_id = be_u32(&record[32..36]);
+    let name_bytes = &record[44..63];
+    let name = String::from_utf8_lossy(name_bytes)
+        .trim_end_matches(|c: char| c == '\0' || c.is_whitespace())
+        .to_string();
+
+    SdbDevice {
+        name,
+        vendor_id,
+        device_id,
+        addr_first,
+        addr_last,
+    }
+}
+
+impl Bridge {
+    /// Read a 64-byte SDB record starting at `addr`
+    fn read_sdb_record(&self, addr: u32) -> Result<[u8; RECORD_LEN], Error> {
+        let word_count = RECORD_LEN / 4;
+        let addrs: Vec<u32> = (0..word_count as u32).map(|i| addr + i * 4).collect();
+        let words = self.read_burst(&addrs)?;
+
+        let mut record = [0u8; RECORD_LEN];
+        for (i, word) in words.iter().enumerate() {
+            record[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        Ok(record)
+    }
+
+    /// Walk the SDB ROM at `sdb_addr` and return every device it describes
+    pub fn enumerate(&self, sdb_addr: u32) -> Result<Vec<SdbDevice>, Error> {
+        let header = self.read_sdb_record(sdb_addr)?;
+
+        let magic = be_u32(&header[0..4]);
+        if magic != MAGIC {
+            return Err(Error::Protocol(format!(
+                "bad SDB magic at 0x{:08x}: 0x{:08x}",
+                sdb_addr, magic
+            )));
+        }
+        // record_count includes the header record itself, so the device
+        // records occupy offsets 1..record_count
+        let record_count = u16::from_be_bytes([header[4], header[5]]) as u32;
+
+        let mut devices = Vec::new();
+        for i in 1..record_count {
+            let record_addr = sdb_addr + i * RECORD_LEN as u32;
+            let record = self.read_sdb_record(record_addr)?;
+            if record[RECORD_LEN - 1] == RECORD_TYPE_DEVICE {
+                devices.push(parse_device(&record));
+            }
+        }
+        Ok(devices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    /// Build a 64-byte SDB device record with the given fields
+    fn device_record(addr_first: u64, addr_last: u64, vendor_id: u64, name: &str) -> [u8; RECORD_LEN] {
+        let mut record = [0u8; RECORD_LEN];
+        record[8..16].copy_from_slice(&addr_first.to_be_bytes());
+        record[16..24].copy_from_slice(&addr_last.to_be_bytes());
+        record[24..32].copy_from_slice(&vendor_id.to_be_bytes());
+        let name_bytes = name.as_bytes();
+        record[44..44 + name_bytes.len()].copy_from_slice(name_bytes);
+        record[RECORD_LEN - 1] = RECORD_TYPE_DEVICE;
+        record
+    }
+
+    /// Build the SDB header record for a table holding `record_count`
+    /// records total, including this header
+    fn header_record(record_count: u16) -> [u8; RECORD_LEN] {
+        let mut record = [0u8; RECORD_LEN];
+        record[0..4].copy_from_slice(&MAGIC.to_be_bytes());
+        record[4..6].copy_from_slice(&record_count.to_be_bytes());
+        record
+    }
+
+    fn write_record(bridge: &Bridge, addr: u32, record: &[u8; RECORD_LEN]) {
+        let words: Vec<u32> = record
+            .chunks(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap()))
+            .collect();
+        bridge.write_burst(addr, &words).unwrap();
+    }
+
+    #[test]
+    fn test_parse_device() {
+        let record = device_record(0x1000, 0x1fff, 0xdead_beef_dead_beef, "uart");
+        let device = parse_device(&record);
+        assert_eq!(device.addr_first, 0x1000);
+        assert_eq!(device.addr_last, 0x1fff);
+        assert_eq!(device.vendor_id, 0xdead_beef_dead_beef);
+        assert_eq!(device.name, "uart");
+    }
+
+    #[test]
+    fn test_enumerate_stops_before_record_count_and_ignores_trailing_record() {
+        let bridge = Bridge::with_transport(Box::new(MockTransport::new()));
+        let sdb_addr = 0x8000_0000;
+
+        // record_count == 3: the header plus exactly two device records.
+        write_record(&bridge, sdb_addr, &header_record(3));
+        write_record(
+            &bridge,
+            sdb_addr + RECORD_LEN as u32,
+            &device_record(0x1000, 0x1fff, 1, "csr0"),
+        );
+        write_record(
+            &bridge,
+            sdb_addr + 2 * RECORD_LEN as u32,
+            &device_record(0x2000, 0x2fff, 2, "csr1"),
+        );
+        // One past the end of the table: a stray device-shaped record
+        // that must NOT be picked up.
+        write_record(
+            &bridge,
+            sdb_addr + 3 * RECORD_LEN as u32,
+            &device_record(0xffff, 0xffff, 0xff, "garbage"),
+        );
+
+        let devices = bridge.enumerate(sdb_addr).unwrap();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].name, "csr0");
+        assert_eq!(devices[1].name, "csr1");
+    }
+}
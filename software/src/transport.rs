@@ -0,0 +1,215 @@
+//! Pluggable Etherbone transports
+//!
+//! `Bridge` talks to an Etherbone endpoint through this trait rather than
+//! being hard-wired to the FT601 USB device, so the same encode/decode and
+//! register API works against local hardware or a remote LiteX/Wishbone
+//! core over the network.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, UdpSocket};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::usb;
+use crate::Error;
+
+/// An Etherbone-capable transport: something that can carry an encoded
+/// packet to the far end and, for `transact`, wait for the reply.
+pub trait Transport {
+    /// Send a request and wait for the response
+    fn transact(&self, channel: u8, req: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error>;
+    /// Send a request without waiting for a response
+    fn send(&self, channel: u8, req: &[u8]) -> Result<(), Error>;
+}
+
+impl Transport for usb::Device {
+    fn transact(&self, channel: u8, req: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error> {
+        usb::Device::transact(self, channel, req, timeout_ms)
+    }
+
+    fn send(&self, channel: u8, req: &[u8]) -> Result<(), Error> {
+        usb::Device::send(self, channel, req)
+    }
+}
+
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(
+        e.kind(),
+        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+    )
+}
+
+/// Etherbone over UDP, the wire format used by LiteX's `litex_server`
+/// and `wishbone-tool`. The FT601 channel is meaningless here - standard
+/// Etherbone has no channel multiplexing - so it is ignored.
+pub struct UdpTransport {
+    socket: UdpSocket,
+}
+
+impl UdpTransport {
+    /// Connect to a remote Etherbone host, e.g. "192.168.1.50:1234"
+    pub fn connect(addr: &str) -> Result<Self, Error> {
+        let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| Error::Usb(e.to_string()))?;
+        socket
+            .connect(addr)
+            .map_err(|e| Error::Usb(e.to_string()))?;
+        Ok(UdpTransport { socket })
+    }
+}
+
+impl Transport for UdpTransport {
+    fn transact(&self, channel: u8, req: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error> {
+        self.send(channel, req)?;
+        self.socket
+            .set_read_timeout(Some(Duration::from_millis(timeout_ms as u64)))
+            .map_err(|e| Error::Usb(e.to_string()))?;
+
+        let mut buf = [0u8; 4096];
+        match self.socket.recv(&mut buf) {
+            Ok(n) => Ok(buf[..n].to_vec()),
+            Err(e) if is_timeout(&e) => Err(Error::Timeout),
+            Err(e) => Err(Error::Usb(e.to_string())),
+        }
+    }
+
+    fn send(&self, _channel: u8, req: &[u8]) -> Result<(), Error> {
+        self.socket.send(req).map_err(|e| Error::Usb(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Etherbone over TCP, the wire format `litex-server` speaks to
+/// `litex_cli`/`RemoteClient`. The FT601 channel is ignored, as above.
+pub struct TcpTransport {
+    stream: Mutex<TcpStream>,
+}
+
+impl TcpTransport {
+    /// Connect to a remote Etherbone host, e.g. "192.168.1.50:1234"
+    pub fn connect(addr: &str) -> Result<Self, Error> {
+        let stream = TcpStream::connect(addr).map_err(|e| Error::Usb(e.to_string()))?;
+        Ok(TcpTransport {
+            stream: Mutex::new(stream),
+        })
+    }
+}
+
+impl Transport for TcpTransport {
+    fn transact(&self, channel: u8, req: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error> {
+        let mut stream = self.stream.lock().unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_millis(timeout_ms as u64)))
+            .map_err(|e| Error::Usb(e.to_string()))?;
+        self.send_locked(&mut stream, channel, req)?;
+
+        let mut buf = [0u8; 4096];
+        match stream.read(&mut buf) {
+            Ok(0) => Err(Error::Usb("connection closed".into())),
+            Ok(n) => Ok(buf[..n].to_vec()),
+            Err(e) if is_timeout(&e) => Err(Error::Timeout),
+            Err(e) => Err(Error::Usb(e.to_string())),
+        }
+    }
+
+    fn send(&self, channel: u8, req: &[u8]) -> Result<(), Error> {
+        let mut stream = self.stream.lock().unwrap();
+        self.send_locked(&mut stream, channel, req)
+    }
+}
+
+impl TcpTransport {
+    fn send_locked(&self, stream: &mut TcpStream, _channel: u8, req: &[u8]) -> Result<(), Error> {
+        stream.write_all(req).map_err(|e| Error::Usb(e.to_string()))
+    }
+}
+
+/// An in-memory fake [`Transport`] backing a Wishbone address space, so
+/// `Bridge` logic can be exercised without real hardware. Shared by other
+/// modules' tests (e.g. [`crate::image`]) via `pub(crate)`.
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use crate::etherbone::{Packet, Record};
+    use std::collections::HashMap;
+
+    pub(crate) struct MockTransport {
+        memory: Mutex<HashMap<u32, u32>>,
+    }
+
+    impl MockTransport {
+        pub(crate) fn new() -> Self {
+            MockTransport {
+                memory: Mutex::new(HashMap::new()),
+            }
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn transact(&self, _channel: u8, req: &[u8], _timeout_ms: u32) -> Result<Vec<u8>, Error> {
+            let packet =
+                Packet::decode(req).ok_or_else(|| Error::Protocol("bad request".into()))?;
+            let mut memory = self.memory.lock().unwrap();
+            let mut response = Packet::new();
+
+            for record in &packet.records {
+                if let Some((base_addr, data)) = &record.writes {
+                    for (i, &val) in data.iter().enumerate() {
+                        let addr = if record.wff() {
+                            *base_addr
+                        } else {
+                            base_addr + (i as u32) * 4
+                        };
+                        memory.insert(addr, val);
+                    }
+                }
+                if let Some((base_ret_addr, addrs)) = &record.reads {
+                    let values: Vec<u32> =
+                        addrs.iter().map(|a| *memory.get(a).unwrap_or(&0)).collect();
+                    response.records.push(Record::read_response(*base_ret_addr, values));
+                }
+            }
+
+            Ok(response.encode())
+        }
+
+        fn send(&self, channel: u8, req: &[u8]) -> Result<(), Error> {
+            self.transact(channel, req, 0).map(|_| ())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockTransport;
+    use super::*;
+    use crate::etherbone;
+
+    #[test]
+    fn test_mock_transport_write_then_read_roundtrip() {
+        let transport = MockTransport::new();
+        let write = etherbone::Packet::write(0x1000, 0xdead_beef).encode();
+        transport.transact(0, &write, 0).unwrap();
+
+        let read = etherbone::Packet::read(0x1000).encode();
+        let response = transport.transact(0, &read, 0).unwrap();
+        let decoded = etherbone::Packet::decode(&response).unwrap();
+
+        assert_eq!(
+            decoded.records[0].writes.clone().unwrap().1,
+            vec![0xdead_beef]
+        );
+    }
+
+    #[test]
+    fn test_is_timeout_classifies_wouldblock_and_timedout() {
+        assert!(is_timeout(&std::io::Error::from(
+            std::io::ErrorKind::WouldBlock
+        )));
+        assert!(is_timeout(&std::io::Error::from(
+            std::io::ErrorKind::TimedOut
+        )));
+        assert!(!is_timeout(&std::io::Error::from(
+            std::io::ErrorKind::Other
+        )));
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! Low-level USB communication with FT601 using the streaming protocol.
 
+use d3xx::notification::{Notification, NotificationData};
 use d3xx::{list_devices, Device as D3xxDevice, Pipe};
 use std::io::{Read, Write};
-use std::time::Duration;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use crate::Error;
 
@@ -16,7 +19,11 @@ pub const STREAM_HEADER_SIZE: usize = 12;
 
 /// FT601 USB device wrapper
 pub struct Device {
-    inner: D3xxDevice,
+    inner: Arc<D3xxDevice>,
+    /// Notification-driven receive queue, populated by the callback
+    /// registered in `open`. `None` if the chip config has notifications
+    /// disabled and `recv` must fall back to polling.
+    notifications: Option<Receiver<(u8, Vec<u8>)>>,
 }
 
 /// Device information
@@ -48,7 +55,7 @@ impl Device {
         }
 
         let inner = devices[0].open().map_err(|e| Error::Usb(e.to_string()))?;
-        Ok(Device { inner })
+        Self::from_inner(inner)
     }
 
     /// Open a specific device by index
@@ -62,7 +69,36 @@ impl Device {
         let inner = devices[index]
             .open()
             .map_err(|e| Error::Usb(e.to_string()))?;
-        Ok(Device { inner })
+        Self::from_inner(inner)
+    }
+
+    /// Wrap an opened d3xx device, wiring up the notification callback
+    /// that drives `recv`. If the chip config has notifications disabled,
+    /// registering the callback fails and `recv` falls back to polling.
+    fn from_inner(inner: D3xxDevice) -> Result<Self, Error> {
+        let inner = Arc::new(inner);
+        let (tx, rx) = mpsc::channel();
+        let callback_device = inner.clone();
+
+        let notifications = match inner.set_notification_callback(
+            move |notification: Notification<()>| {
+                if let NotificationData::Data { endpoint, size } = notification.data() {
+                    let mut buf = vec![0u8; *size];
+                    let mut pipe = callback_device.pipe(*endpoint);
+                    if let Ok(n) = pipe.read(&mut buf) {
+                        if let Some(packet) = unwrap_packet(&buf[..n]) {
+                            let _ = tx.send(packet);
+                        }
+                    }
+                }
+            },
+            None,
+        ) {
+            Ok(()) => Some(rx),
+            Err(_) => None,
+        };
+
+        Ok(Device { inner, notifications })
     }
 
     /// Send a packet with USB streaming header
@@ -76,7 +112,28 @@ impl Device {
 
     /// Receive a packet, stripping the USB streaming header
     /// Returns (channel, payload) or None if no data available
+    ///
+    /// Blocks on the notification queue populated by the callback set up
+    /// in `open`, woken at notification latency instead of spinning. Falls
+    /// back to a polling read if notifications are disabled in the chip
+    /// config (i.e. the callback failed to register).
     pub fn recv(&self, timeout_ms: u32) -> Result<Option<(u8, Vec<u8>)>, Error> {
+        let timeout = Duration::from_millis(timeout_ms as u64);
+
+        match &self.notifications {
+            Some(rx) => match rx.recv_timeout(timeout) {
+                Ok(packet) => Ok(Some(packet)),
+                Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    Err(Error::Usb("notification channel closed".into()))
+                }
+            },
+            None => self.recv_polling(timeout_ms),
+        }
+    }
+
+    /// Busy-wait fallback used when notifications are unavailable
+    fn recv_polling(&self, timeout_ms: u32) -> Result<Option<(u8, Vec<u8>)>, Error> {
         let mut buf = [0u8; 4096];
         let start = std::time::Instant::now();
         let timeout = Duration::from_millis(timeout_ms as u64);